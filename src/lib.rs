@@ -10,8 +10,45 @@
 #![warn(rustdoc::invalid_codeblock_attributes)]
 #![warn(rustdoc::invalid_html_tags)]
 
-use bevy::ecs::event::Event;
+use std::any::TypeId;
+use std::fmt::Debug;
+
+use bevy::ecs::event::{Event, ManualEventReader};
+use bevy::ecs::schedule::IntoSystemDescriptor;
+use bevy::ecs::system::{ExclusiveSystem, IntoExclusiveSystem, System};
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// A resource that caches the [`ManualEventReader`] used by
+/// [`TestApp::collect_events`], so that successive calls continue reading
+/// from where the previous call left off instead of rereading every event
+/// still sitting in the double buffer.
+struct EventReaderCache<E: Event> {
+    /// The reader's cursor into the app's `Events<E>` resource.
+    reader: ManualEventReader<E>,
+}
+
+impl<E: Event> Default for EventReaderCache<E> {
+    fn default() -> Self {
+        Self {
+            reader: ManualEventReader::default(),
+        }
+    }
+}
+
+/// A resource that caches initialized systems, keyed by the [`TypeId`] of the
+/// system itself, so that change detection ticks and [`Local`] state persist
+/// across repeated invocations via [`TestApp::run_cached_system`].
+///
+/// Without this cache, each call would build and initialize a brand new
+/// system, resetting its last-run change tick and discarding any `Local`
+/// state along with it.
+#[derive(Default)]
+struct SystemRegistry {
+    /// The cached, boxed, and already-initialized systems, keyed by the
+    /// [`TypeId`] of the system they were built from.
+    systems: HashMap<TypeId, Box<dyn System<In = (), Out = ()>>>,
+}
 
 /// An extension for the standard Bevy app that adds more unit test helper
 /// functions.
@@ -29,12 +66,127 @@ pub trait TestApp {
     /// The systems are not added to the app.
     fn run_systems_once<Params>(&mut self, system: Vec<impl IntoSystemDescriptor<Params>>);
 
-    /// Collects all events of the indicated type currently within the system
-    /// and returns an iterator over all of them.
+    /// Causes the provided system to be executed once, immediately on the
+    /// current thread, feeding it the given input value and returning
+    /// whatever the system produces.
+    ///
+    /// The system is not added to the app.
+    fn run_system_once_with<In, Out, Params>(
+        &mut self,
+        input: In,
+        system: impl IntoSystem<In, Out, Params>,
+    ) -> Out;
+
+    /// Causes the provided system to be executed once, immediately on the
+    /// current thread, returning whatever the system produces.
+    ///
+    /// The system is not added to the app.
+    fn run_system_once_returning<Out, Params>(
+        &mut self,
+        system: impl IntoSystem<(), Out, Params>,
+    ) -> Out;
+
+    /// Causes the provided system to be executed once, immediately on the
+    /// current thread, reusing the same initialized system instance across
+    /// repeated calls.
+    ///
+    /// Unlike [`TestApp::run_system_once`], which rebuilds and initializes the
+    /// system from scratch on every call, this caches the system keyed by its
+    /// [`TypeId`], so that change detection (`Added<T>`/`Changed<T>`) and
+    /// `Local<T>` state behave the same as they would inside a normal
+    /// schedule spanning multiple frames.
+    ///
+    /// # Captured state hazard
     ///
-    /// Note that the events are still removed from the app, even the iterator
-    /// is not used.
+    /// The cache is keyed purely on the **type** of `S`, not on any state it
+    /// captures. Only the `system` argument passed on the *first* call for a
+    /// given type is ever constructed and run; on every later call the
+    /// `system` argument is silently dropped and the original instance keeps
+    /// running in its place. A closure that captures per-call state, e.g.
+    /// `let n = i; app.run_cached_system(move |mut local: Local<i32>| *local
+    /// += n)` inside a loop, will therefore keep executing the first
+    /// iteration's closure forever. Only pass stateless closures or `fn`
+    /// items here; call [`TestApp::clear_cached_systems`] first if you need
+    /// the next call to register a fresh instance.
+    ///
+    /// The system is not added to the app.
+    fn run_cached_system<S, Params>(&mut self, system: S)
+    where
+        S: IntoSystem<(), (), Params> + 'static;
+
+    /// Drops every system cached by [`TestApp::run_cached_system`], so the
+    /// next call for any given system type initializes and runs a fresh
+    /// instance instead of reusing a stale one.
+    fn clear_cached_systems(&mut self);
+
+    /// Causes the provided exclusive system to be executed once, immediately
+    /// on the current thread, with direct `&mut World` access.
+    ///
+    /// This allows tests that need to spawn scenes, mutate several resources
+    /// atomically, or otherwise reach into the world directly, to do so
+    /// through a single closure rather than poking at `app.world` by hand.
+    /// Accepts anything `IntoExclusiveSystem` accepts: a plain `FnMut(&mut
+    /// World)` closure, or any ordinary system coerced via
+    /// `.exclusive_system()`.
+    ///
+    /// The system is not added to the app.
+    fn run_exclusive_system_once<Params, SystemType: ExclusiveSystem>(
+        &mut self,
+        system: impl IntoExclusiveSystem<Params, SystemType>,
+    );
+
+    /// Calls [`App::update`] repeatedly until `predicate` returns `true` or
+    /// `max_iters` updates have been run.
+    ///
+    /// The predicate is checked after each update and is given read access to
+    /// the app's world, so tests can assert on resources or components that
+    /// only settle after several frames (timers, state transitions,
+    /// command-driven spawns that resolve next frame, and the like).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_iters` updates run without the predicate returning
+    /// `true`, so a broken predicate fails the test instead of hanging CI.
+    fn run_until(&mut self, predicate: impl FnMut(&World) -> bool, max_iters: usize);
+
+    /// Calls [`App::update`] exactly `n` times.
+    fn run_frames(&mut self, n: usize);
+
+    /// Collects all events of the indicated type sent since the last call to
+    /// `collect_events::<E>` (or since the app was created, for the first
+    /// call), and returns an iterator over all of them.
+    ///
+    /// The reader's cursor is persisted across calls, so an event is only
+    /// ever yielded once, even if the iterator returned by a previous call
+    /// was not consumed.
+    ///
+    /// Only events still held in the `Events<E>` double buffer are returned;
+    /// events that buffer has already dropped (after two of its updates) are
+    /// skipped.
     fn collect_events<E: Event + Clone>(&mut self) -> Box<dyn Iterator<Item = E>>;
+
+    /// Asserts that exactly `expected` events of the indicated type have been
+    /// sent since the last time they were read.
+    ///
+    /// Like [`TestApp::collect_events`], this advances the `E` reader's
+    /// cursor, so a later call only counts events sent after this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of events does not match `expected`.
+    fn assert_event_count<E: Event + Clone>(&mut self, expected: usize);
+
+    /// Sends the given event, initializing the `Events<E>` resource first if
+    /// it is not already present.
+    fn send_event<E: Event>(&mut self, event: E);
+
+    /// Asserts that exactly one event of the indicated type has been sent
+    /// since the last time it was read, and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if zero or more than one event of this type was emitted.
+    fn expect_single_event<E: Event + Clone + Debug>(&mut self) -> E;
 }
 
 impl TestApp for App {
@@ -51,15 +203,336 @@ impl TestApp for App {
         stage.run(&mut self.world);
     }
 
+    fn run_system_once_with<In, Out, Params>(
+        &mut self,
+        input: In,
+        system: impl IntoSystem<In, Out, Params>,
+    ) -> Out {
+        let mut system = IntoSystem::into_system(system);
+        system.initialize(&mut self.world);
+        let out = system.run(input, &mut self.world);
+        system.apply_buffers(&mut self.world);
+        out
+    }
+
+    fn run_system_once_returning<Out, Params>(
+        &mut self,
+        system: impl IntoSystem<(), Out, Params>,
+    ) -> Out {
+        self.run_system_once_with((), system)
+    }
+
+    fn run_cached_system<S, Params>(&mut self, system: S)
+    where
+        S: IntoSystem<(), (), Params> + 'static,
+    {
+        // Bump the tick before running, not after `apply_buffers`: the cached
+        // system's `last_change_tick` is set to whatever tick was current
+        // during `run`, so anything this same call inserts via `Commands`
+        // must land on a *later* tick than that, or the system will never see
+        // its own just-inserted components as `Added`/`Changed` the next
+        // time it runs.
+        self.world.increment_change_tick();
+        self.world.init_resource::<SystemRegistry>();
+
+        let type_id = TypeId::of::<S>();
+        let mut registry = self.world.remove_resource::<SystemRegistry>().unwrap();
+
+        let cached = registry.systems.entry(type_id).or_insert_with(|| {
+            let mut system = IntoSystem::into_system(system);
+            system.initialize(&mut self.world);
+            Box::new(system)
+        });
+
+        cached.run((), &mut self.world);
+        cached.apply_buffers(&mut self.world);
+
+        self.world.insert_resource(registry);
+    }
+
+    fn clear_cached_systems(&mut self) {
+        self.world.remove_resource::<SystemRegistry>();
+    }
+
+    fn run_exclusive_system_once<Params, SystemType: ExclusiveSystem>(
+        &mut self,
+        system: impl IntoExclusiveSystem<Params, SystemType>,
+    ) {
+        let mut system = system.exclusive_system();
+        system.initialize(&mut self.world);
+        system.run(&mut self.world);
+    }
+
+    fn run_until(&mut self, mut predicate: impl FnMut(&World) -> bool, max_iters: usize) {
+        for _ in 0..max_iters {
+            self.update();
+
+            if predicate(&self.world) {
+                return;
+            }
+        }
+
+        panic!("run_until did not satisfy its predicate within {max_iters} update(s)");
+    }
+
+    fn run_frames(&mut self, n: usize) {
+        for _ in 0..n {
+            self.update();
+        }
+    }
+
     fn collect_events<E: Event + Clone>(&mut self) -> Box<dyn Iterator<Item = E>> {
+        self.world.init_resource::<EventReaderCache<E>>();
+        self.world.init_resource::<Events<E>>();
+        let mut cache = self.world.remove_resource::<EventReaderCache<E>>().unwrap();
+
         let event_res = self.world.resource::<Events<E>>();
-        let mut event_reader = event_res.get_reader();
-        Box::new(
-            event_reader
-                .iter(event_res)
-                .map(|e| (*e).clone())
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        let events = cache
+            .reader
+            .iter(event_res)
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        self.world.insert_resource(cache);
+        Box::new(events)
+    }
+
+    fn assert_event_count<E: Event + Clone>(&mut self, expected: usize) {
+        let count = self.collect_events::<E>().count();
+        assert_eq!(count, expected, "expected {expected} event(s), found {count}");
+    }
+
+    fn send_event<E: Event>(&mut self, event: E) {
+        self.world.init_resource::<Events<E>>();
+        self.world.resource_mut::<Events<E>>().send(event);
+    }
+
+    fn expect_single_event<E: Event + Clone + Debug>(&mut self) -> E {
+        let mut events: Vec<E> = self.collect_events().collect();
+        assert_eq!(
+            events.len(),
+            1,
+            "expected exactly one event, found {}: {:?}",
+            events.len(),
+            events
+        );
+        events.remove(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(In(x): In<i32>) -> i32 {
+        x * 2
+    }
+
+    #[test]
+    fn run_system_once_with_feeds_input_and_returns_output() {
+        let mut app = App::new();
+
+        let result = app.run_system_once_with(5, double);
+
+        assert_eq!(result, 10);
+    }
+
+    fn answer() -> i32 {
+        42
+    }
+
+    #[test]
+    fn run_system_once_returning_returns_output() {
+        let mut app = App::new();
+
+        let result = app.run_system_once_returning(answer);
+
+        assert_eq!(result, 42);
+    }
+
+    #[derive(Component)]
+    struct Spawned;
+
+    fn spawn_marker(mut commands: Commands) {
+        commands.spawn().insert(Spawned);
+    }
+
+    #[test]
+    fn run_system_once_with_applies_queued_commands() {
+        let mut app = App::new();
+
+        app.run_system_once_with((), spawn_marker);
+
+        assert_eq!(app.world.query::<&Spawned>().iter(&app.world).count(), 1);
+    }
+
+    #[derive(Default)]
+    struct Counter(i32);
+
+    fn increment(mut local: Local<i32>, mut counter: ResMut<Counter>) {
+        *local += 1;
+        counter.0 = *local;
+    }
+
+    #[test]
+    fn run_cached_system_persists_local_state() {
+        let mut app = App::new();
+        app.insert_resource(Counter::default());
+
+        app.run_cached_system(increment);
+        app.run_cached_system(increment);
+        app.run_cached_system(increment);
+
+        assert_eq!(app.world.resource::<Counter>().0, 3);
+    }
+
+    #[test]
+    fn clear_cached_systems_forces_reinitialization() {
+        let mut app = App::new();
+        app.insert_resource(Counter::default());
+
+        app.run_cached_system(increment);
+        app.run_cached_system(increment);
+        assert_eq!(app.world.resource::<Counter>().0, 2);
+
+        app.clear_cached_systems();
+        app.run_cached_system(increment);
+        assert_eq!(app.world.resource::<Counter>().0, 1);
+    }
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[derive(Default)]
+    struct AddedLog(Vec<bool>);
+
+    fn spawn_once_and_log_added(
+        mut commands: Commands,
+        mut log: ResMut<AddedLog>,
+        mut spawned: Local<bool>,
+        query: Query<&Marker, Added<Marker>>,
+    ) {
+        log.0.push(!query.is_empty());
+
+        if !*spawned {
+            commands.spawn().insert(Marker);
+            *spawned = true;
+        }
+    }
+
+    #[test]
+    fn run_cached_system_detects_its_own_commands_on_the_next_run() {
+        let mut app = App::new();
+        app.insert_resource(AddedLog::default());
+
+        app.run_cached_system(spawn_once_and_log_added);
+        app.run_cached_system(spawn_once_and_log_added);
+        app.run_cached_system(spawn_once_and_log_added);
+
+        assert_eq!(app.world.resource::<AddedLog>().0, vec![false, true, false]);
+    }
+
+    fn exclusive_spawn_marker(world: &mut World) {
+        world.spawn().insert(Marker);
+    }
+
+    #[test]
+    fn run_exclusive_system_once_mutates_the_world_directly() {
+        let mut app = App::new();
+
+        app.run_exclusive_system_once(exclusive_spawn_marker);
+
+        assert_eq!(app.world.query::<&Marker>().iter(&app.world).count(), 1);
+    }
+
+    fn coerced_spawn_marker(mut commands: Commands) {
+        commands.spawn().insert(Marker);
+    }
+
+    #[test]
+    fn run_exclusive_system_once_supports_ordinary_systems_coerced_to_exclusive() {
+        let mut app = App::new();
+
+        app.run_exclusive_system_once(coerced_spawn_marker);
+
+        assert_eq!(app.world.query::<&Marker>().iter(&app.world).count(), 1);
+    }
+
+    #[derive(Clone, Debug)]
+    struct Ping(i32);
+
+    #[test]
+    fn assert_event_count_drains_and_persists_its_cursor() {
+        let mut app = App::new();
+
+        app.send_event(Ping(1));
+        app.assert_event_count::<Ping>(1);
+        app.assert_event_count::<Ping>(0);
+    }
+
+    #[test]
+    fn assert_event_count_on_an_unregistered_event_type_does_not_panic() {
+        let mut app = App::new();
+
+        app.assert_event_count::<Ping>(0);
+    }
+
+    #[test]
+    fn expect_single_event_returns_the_event() {
+        let mut app = App::new();
+
+        app.send_event(Ping(7));
+        let event = app.expect_single_event::<Ping>();
+        assert_eq!(event.0, 7);
+    }
+
+    #[test]
+    fn expect_single_event_does_not_require_partial_eq() {
+        #[derive(Clone, Debug)]
+        struct NoEq(i32);
+
+        let mut app = App::new();
+
+        app.send_event(NoEq(3));
+        let event = app.expect_single_event::<NoEq>();
+        assert_eq!(event.0, 3);
+    }
+
+    #[derive(Default)]
+    struct FrameCount(usize);
+
+    fn bump_frame_count(mut count: ResMut<FrameCount>) {
+        count.0 += 1;
+    }
+
+    #[test]
+    fn run_frames_calls_update_exactly_n_times() {
+        let mut app = App::new();
+        app.insert_resource(FrameCount::default());
+        app.add_system(bump_frame_count);
+
+        app.run_frames(3);
+
+        assert_eq!(app.world.resource::<FrameCount>().0, 3);
+    }
+
+    #[test]
+    fn run_until_stops_once_predicate_is_satisfied() {
+        let mut app = App::new();
+        app.insert_resource(FrameCount::default());
+        app.add_system(bump_frame_count);
+
+        app.run_until(|world| world.resource::<FrameCount>().0 >= 3, 10);
+
+        assert_eq!(app.world.resource::<FrameCount>().0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "run_until did not satisfy its predicate within 2 update(s)")]
+    fn run_until_panics_when_max_iters_is_exceeded() {
+        let mut app = App::new();
+
+        app.run_until(|_| false, 2);
     }
 }